@@ -1,15 +1,17 @@
 use bytesize::ByteSize;
 use clap::{Parser, ValueEnum};
 use colored::Colorize;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
 use std::{
     cmp::Reverse,
     env::{self},
     error::Error,
-    ffi::OsStr,
     fmt::Display,
-    fs::{self, File},
+    fs,
     os::unix::fs::MetadataExt,
     path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 #[derive(Parser)]
@@ -19,6 +21,29 @@ struct Args {
     delete: bool,
     #[arg(short, long)]
     language: Option<RepoLanguage>,
+    #[arg(long, default_value = "text")]
+    format: Format,
+    /// Only delete repos whose newest source file is at least this many days
+    /// old; more recently touched checkouts are listed but left untouched.
+    #[arg(long)]
+    older_than: Option<u64>,
+    /// Glob(s) of directories to skip while scanning, e.g. `--exclude '*.cache'`.
+    #[arg(long)]
+    exclude: Vec<String>,
+    /// Do not honour `.gitignore` files encountered during the scan.
+    #[arg(long)]
+    no_gitignore: bool,
+    /// Path to a `clean-deps.toml`; by default one is searched for by walking
+    /// up from the scan root.
+    #[arg(long)]
+    config: Option<PathBuf>,
+}
+
+#[derive(Clone, ValueEnum, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
@@ -26,78 +51,263 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let root = args.path.unwrap_or(env::current_dir()?);
 
-    let mut repos = find_repos(root)?;
+    let mut table = LanguageTable::builtin();
 
-    if let Some(language) = args.language {
-        repos.retain(|r| r.language == language);
+    if let Some(path) = args.config.clone().or_else(|| find_config(&root)) {
+        let content = fs::read_to_string(&path)?;
+        table.merge(toml::from_str(&content)?);
     }
 
-    repos.sort_by_key(|a| Reverse(a.deps_size));
+    let options = ScanOptions {
+        exclude: args
+            .exclude
+            .iter()
+            .map(|glob| glob::Pattern::new(glob))
+            .collect::<Result<Vec<_>, _>>()?,
+        respect_gitignore: !args.no_gitignore,
+        track_mtime: args.older_than.is_some(),
+        table,
+    };
+
+    let mut repos = find_repos(root, &options);
 
-    for repo in &repos {
-        println!("{repo}");
+    if let Some(language) = args.language {
+        repos.retain(|r| r.language == language);
     }
 
-    let total_size = &repos
+    repos.sort_by_key(|a| (Reverse(a.deps_size), a.dir.clone()));
+
+    let total_size = repos
         .iter()
         .map(|r| r.deps_size)
         .fold(ByteSize(0), |a, b| a + b);
 
-    println!();
+    match args.format {
+        Format::Text => {
+            for repo in &repos {
+                println!("{repo}");
+            }
 
-    println!("Total size: {total_size}");
+            println!();
+
+            println!("Total size: {total_size}");
+        }
+        Format::Json => {
+            let report = Report {
+                total: total_size,
+                repos: &repos,
+            };
+
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+        Format::Csv => {
+            println!("language,path,bytes,human");
+
+            for repo in &repos {
+                println!(
+                    "{},{},{},{}",
+                    csv_quote(repo.language.name()),
+                    csv_quote(&repo.dir.to_string_lossy()),
+                    repo.deps_size.0,
+                    repo.deps_size
+                );
+            }
+        }
+    }
 
     if args.delete {
         println!();
         println!("Removing dependencies:");
 
+        let cutoff = match args.older_than {
+            Some(days) => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+                Some(now - (days as i64) * 86_400)
+            }
+            None => None,
+        };
+
         for repo in repos {
-            repo.delete_deps()?;
+            if let Some(cutoff) = cutoff {
+                if repo.newest_mtime > cutoff {
+                    println!("Skipping recent: {}", truncate_path_for_display(&repo.dir, 60));
+                    continue;
+                }
+            }
+
+            repo.delete_deps(&options.table)?;
         }
     }
 
     Ok(())
 }
 
-fn find_repos(path: PathBuf) -> Result<Vec<Repo>, Box<dyn Error>> {
-    let mut repos = vec![];
+/// VCS metadata directories: never a dependency tree, never worth descending.
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".svn"];
+
+/// Options controlling which directories the traversal descends into.
+struct ScanOptions {
+    exclude: Vec<glob::Pattern>,
+    respect_gitignore: bool,
+    table: LanguageTable,
+    /// Whether to record each repo's newest source mtime; only needed when
+    /// `--older-than` is in play, and an extra serial walk otherwise.
+    track_mtime: bool,
+}
+
+impl ScanOptions {
+    /// Whether `path` should be pruned rather than descended into: dependency
+    /// output dirs, VCS metadata, user `--exclude` globs, and — unless
+    /// disabled — entries matched by the `.gitignore` of the directory being
+    /// listed (patterns from ancestor directories are not carried down).
+    fn is_pruned(&self, path: &Path, owner: Option<&Language>, ignore: &[glob::Pattern]) -> bool {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if VCS_DIRS.contains(&name.as_str()) {
+            return true;
+        }
 
-    if Repo::is_repo(&path) {
-        if let Some(repo) = Repo::new(&path)? {
-            repos.push(repo);
+        if owner.is_some_and(|language| language.matches_dep_dir(&name)) {
+            return true;
         }
+
+        let path_str = path.to_string_lossy();
+
+        self.exclude
+            .iter()
+            .chain(ignore)
+            .any(|pattern| pattern.matches(&name) || pattern.matches(&path_str))
     }
+}
 
-    let content = fs::read_dir(&path)?;
+/// Read directory-name patterns from a `.gitignore` in `dir`, if present.
+fn read_gitignore(dir: &Path) -> Vec<glob::Pattern> {
+    match fs::read_to_string(dir.join(".gitignore")) {
+        Ok(content) => parse_gitignore(&content),
+        Err(_) => Vec::new(),
+    }
+}
 
-    for entry in content.flatten() {
-        if entry.file_type()?.is_dir() {
-            repos.extend(find_repos(entry.path())?);
-        }
+/// Parse the concrete directory-name patterns from a `.gitignore`'s contents.
+/// Comments, negations, and any wildcard or nested-path pattern are skipped —
+/// only bare names survive, so a catch-all like `*` can never prune a whole
+/// subtree out from under the scan.
+fn parse_gitignore(content: &str) -> Vec<glob::Pattern> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| line.trim_matches('/'))
+        .filter(|line| !line.is_empty() && !line.contains(['*', '?', '[', '/']))
+        .filter_map(|line| glob::Pattern::new(line).ok())
+        .collect()
+}
+
+fn find_repos(path: PathBuf, options: &ScanOptions) -> Vec<Repo> {
+    let mut repos = vec![];
+
+    let language = options.table.detect(&path).ok().flatten();
+
+    if let Some(kind) = language.clone() {
+        repos.push(Repo::new(&options.table, &path, kind, options.track_mtime));
     }
 
-    Ok(repos)
+    // Only prune dependency dirs belonging to the language this dir was
+    // classified as, so a project nested under an unrelated `build`/`bin` is
+    // still discovered.
+    let owner = language.as_ref().and_then(|kind| options.table.spec(kind));
+
+    let ignore = if options.respect_gitignore {
+        read_gitignore(&path)
+    } else {
+        Vec::new()
+    };
+
+    let children: Vec<PathBuf> = match fs::read_dir(&path) {
+        Ok(content) => content
+            .flatten()
+            .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .map(|entry| entry.path())
+            .filter(|child| !options.is_pruned(child, owner, &ignore))
+            .collect(),
+        Err(_) => return repos,
+    };
+
+    repos.par_extend(
+        children
+            .into_par_iter()
+            .flat_map(|child| find_repos(child, options)),
+    );
+
+    repos
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct Repo {
     language: RepoLanguage,
+    /// Package name read from the project manifest, when one is available.
+    name: Option<String>,
+    #[serde(serialize_with = "serialize_path")]
     dir: PathBuf,
+    #[serde(serialize_with = "serialize_size")]
     deps_size: ByteSize,
+    /// Unix mtime of the newest source file, ignoring the dependency dirs.
+    newest_mtime: i64,
+}
+
+/// A scan report as emitted by the machine-readable output modes: the per-repo
+/// figures plus the overall total, so tooling does not have to re-sum.
+#[derive(Serialize)]
+struct Report<'a> {
+    #[serde(serialize_with = "serialize_size")]
+    total: ByteSize,
+    repos: &'a [Repo],
+}
+
+/// Serialize a size as both its raw byte count and a human-readable string so
+/// consumers can sort on `bytes` while still showing `human` in a dashboard.
+fn serialize_size<S: Serializer>(size: &ByteSize, serializer: S) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Size {
+        bytes: u64,
+        human: String,
+    }
+
+    Size {
+        bytes: size.0,
+        human: size.to_string(),
+    }
+    .serialize(serializer)
+}
+
+/// Serialize a path as its full string form rather than the truncated value
+/// used for the human table, so downstream tooling gets an actionable path.
+fn serialize_path<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&path.to_string_lossy())
 }
 
 impl Display for Repo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{:<10} {:<45}\t{}",
+            "{:<10} {:<20} {:<45}\t{}",
             self.language,
+            self.name.as_deref().unwrap_or("-"),
             truncate_path_for_display(&self.dir, 40),
             self.deps_size
         )
     }
 }
 
+/// Quote a field for CSV output, doubling any embedded double quotes as the
+/// format requires.
+fn csv_quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
 fn truncate_path_for_display(path: &Path, max_length: usize) -> String {
     if let Some(path) = path.to_str() {
         if path.len() <= max_length {
@@ -116,59 +326,46 @@ fn truncate_path_for_display(path: &Path, max_length: usize) -> String {
 }
 
 impl Repo {
-    fn new(path: &Path) -> Result<Option<Self>, Box<dyn Error>> {
-        if let Some(language) = Self::get_language(path)? {
-            Ok(Some(Self {
-                dir: path.to_path_buf(),
-                deps_size: Self::get_deps_size(path, &language)?,
-                language,
-            }))
-        } else {
-            Ok(None)
-        }
-    }
+    fn new(table: &LanguageTable, path: &Path, language: RepoLanguage, track_mtime: bool) -> Self {
+        let dep_paths = table.dep_paths(&language, path);
 
-    fn is_repo(path: &Path) -> bool {
-        matches!(Self::get_language(path), Ok(Some(_)))
+        Self {
+            name: Self::read_name(path, &language),
+            newest_mtime: if track_mtime {
+                newest_source_mtime(path, table)
+            } else {
+                0
+            },
+            deps_size: Self::get_deps_size(&dep_paths),
+            dir: path.to_path_buf(),
+            language,
+        }
     }
 
-    fn get_language(path: &Path) -> Result<Option<RepoLanguage>, Box<dyn Error>> {
-        for entry in fs::read_dir(path)?.flatten() {
-            if entry.file_name().eq_ignore_ascii_case("Cargo.toml") {
-                return Ok(Some(RepoLanguage::Rust));
-            }
-
-            if entry.path().extension() == Some(OsStr::new("sln")) {
-                return Ok(Some(RepoLanguage::Dotnet));
-            }
-
-            if entry.path().extension() == Some(OsStr::new("csproj")) {
-                return Ok(Some(RepoLanguage::Dotnet));
-            }
-
-            if entry.file_name().eq_ignore_ascii_case("package.json") {
-                return Ok(Some(RepoLanguage::Javascript));
+    /// Read the package name from the project manifest, mirroring how the
+    /// crates.rs tarball reader pulls `Package` metadata. Languages without a
+    /// manifest we can parse simply contribute no name.
+    fn read_name(path: &Path, language: &RepoLanguage) -> Option<String> {
+        match language {
+            RepoLanguage::Rust => cargo_toml::Manifest::from_path(path.join("Cargo.toml"))
+                .ok()
+                .and_then(|manifest| manifest.package.map(|package| package.name)),
+            RepoLanguage::Javascript => {
+                let content = fs::read_to_string(path.join("package.json")).ok()?;
+                let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+                value.get("name")?.as_str().map(String::from)
             }
+            _ => None,
         }
-
-        Ok(None)
     }
 
-    fn get_deps_size(path: &Path, language: &RepoLanguage) -> Result<ByteSize, Box<dyn Error>> {
-        let mut size = 0;
-
-        for dp in language.get_dep_paths() {
-            size += get_size(&path.join(dp))?;
-        }
-
-        Ok(ByteSize(size))
+    fn get_deps_size(dep_paths: &[PathBuf]) -> ByteSize {
+        ByteSize(dep_paths.iter().map(|p| get_size(p)).sum())
     }
 
-    fn delete_deps(self) -> Result<(), Box<dyn Error>> {
-        for dp in self.language.get_dep_paths() {
-            let path = self.dir.join(dp);
-
-            if get_size(&path)? > 0 {
+    fn delete_deps(self, table: &LanguageTable) -> Result<(), Box<dyn Error>> {
+        for path in table.dep_paths(&self.language, &self.dir) {
+            if get_size(&path) > 0 {
                 println!("Removing {}", truncate_path_for_display(&path, 60));
                 let _ = fs::remove_dir_all(path);
             } else {
@@ -185,6 +382,29 @@ enum RepoLanguage {
     Dotnet,
     Rust,
     Javascript,
+    Python,
+    Go,
+    Gradle,
+    Maven,
+    /// A language defined in `clean-deps.toml`; not selectable via `--language`.
+    #[value(skip)]
+    Custom(String),
+}
+
+impl RepoLanguage {
+    /// The plain, uncoloured language name used in machine-readable output.
+    fn name(&self) -> &str {
+        match self {
+            Self::Dotnet => "dotnet",
+            Self::Rust => "rust",
+            Self::Javascript => "javascript",
+            Self::Python => "python",
+            Self::Go => "go",
+            Self::Gradle => "gradle",
+            Self::Maven => "maven",
+            Self::Custom(name) => name,
+        }
+    }
 }
 
 impl Display for RepoLanguage {
@@ -196,36 +416,384 @@ impl Display for RepoLanguage {
                 Self::Dotnet => "dotnet".blue(),
                 Self::Javascript => "js".yellow(),
                 Self::Rust => "rust".red(),
+                Self::Python => "python".green(),
+                Self::Go => "go".cyan(),
+                Self::Gradle => "gradle".magenta(),
+                Self::Maven => "maven".bright_blue(),
+                Self::Custom(name) => name.normal(),
             }
         )
     }
 }
 
-impl RepoLanguage {
-    fn get_dep_paths(&self) -> Vec<PathBuf> {
-        match self {
-            Self::Dotnet => vec!["bin".into(), "obj".into()],
-            Self::Javascript => vec!["node_modules".into()],
-            Self::Rust => vec!["target".into()],
+impl Serialize for RepoLanguage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+/// A language known to the cleaner: how to recognise one of its projects and
+/// which directories below the project root are safe to remove. Built from the
+/// built-in table and then extended by any `clean-deps.toml`.
+#[derive(Clone)]
+struct Language {
+    kind: RepoLanguage,
+    /// Marker file names that identify the project root (case-insensitive).
+    markers: Vec<String>,
+    /// Marker file extensions that identify the project root.
+    extensions: Vec<String>,
+    /// Build/dependency directories, relative to the project root. A leading
+    /// `*` is an extension glob matched against directory names, so
+    /// `*.egg-info` cleans every generated egg-info directory.
+    dep_dirs: Vec<String>,
+}
+
+impl Language {
+    fn new(kind: RepoLanguage, markers: &[&str], extensions: &[&str], dep_dirs: &[&str]) -> Self {
+        let owned = |values: &[&str]| values.iter().map(|v| v.to_string()).collect();
+
+        Self {
+            kind,
+            markers: owned(markers),
+            extensions: owned(extensions),
+            dep_dirs: owned(dep_dirs),
+        }
+    }
+
+    /// Whether `name` matches one of this language's dependency directories,
+    /// honouring a leading `*` as an extension glob.
+    fn matches_dep_dir(&self, name: &str) -> bool {
+        self.dep_dirs.iter().any(|dir| match dir.strip_prefix('*') {
+            // A bare `*` (empty suffix) would match every directory; ignore it
+            // rather than let a config entry prune or delete the whole tree.
+            Some(suffix) => !suffix.is_empty() && name.ends_with(suffix),
+            None => dir.as_str() == name,
+        })
+    }
+
+    /// Resolve the concrete dependency directories for a project rooted at
+    /// `dir`, expanding any `*.suffix` globs against the directory's entries.
+    fn dep_paths(&self, dir: &Path) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+
+        for pattern in &self.dep_dirs {
+            if let Some(suffix) = pattern.strip_prefix('*').filter(|s| !s.is_empty()) {
+                if let Ok(content) = fs::read_dir(dir) {
+                    for entry in content.flatten() {
+                        if entry.file_name().to_string_lossy().ends_with(suffix)
+                            && entry.file_type().map(|t| t.is_dir()).unwrap_or(false)
+                        {
+                            paths.push(entry.path());
+                        }
+                    }
+                }
+            } else {
+                paths.push(dir.join(pattern));
+            }
         }
+
+        paths
     }
 }
 
-fn get_size(path: &PathBuf) -> Result<u64, Box<dyn Error>> {
-    let mut size = 0;
-    if let Ok(file) = File::open(path) {
-        if file.metadata()?.is_dir() {
-            for entry in fs::read_dir(path)? {
-                let entry = entry?;
+/// The full language table consulted while scanning. A built-in is one row in
+/// [`LanguageTable::builtin`] plus its [`RepoLanguage`] variant and that
+/// variant's `name`/`Display` arms; users add more via config, with no new
+/// variant.
+#[derive(Clone)]
+struct LanguageTable {
+    languages: Vec<Language>,
+}
+
+impl LanguageTable {
+    fn builtin() -> Self {
+        Self {
+            languages: vec![
+                Language::new(RepoLanguage::Rust, &["Cargo.toml"], &[], &["target"]),
+                Language::new(RepoLanguage::Dotnet, &[], &["sln", "csproj"], &["bin", "obj"]),
+                Language::new(RepoLanguage::Javascript, &["package.json"], &[], &["node_modules"]),
+                Language::new(
+                    RepoLanguage::Python,
+                    &["pyproject.toml", "setup.py", "requirements.txt"],
+                    &[],
+                    &["__pycache__", ".venv", "*.egg-info"],
+                ),
+                Language::new(RepoLanguage::Go, &["go.mod"], &[], &["vendor"]),
+                Language::new(
+                    RepoLanguage::Gradle,
+                    &["build.gradle", "build.gradle.kts", "settings.gradle"],
+                    &[],
+                    &["build", ".gradle"],
+                ),
+                Language::new(RepoLanguage::Maven, &["pom.xml"], &[], &["target"]),
+            ],
+        }
+    }
 
-                size += get_size(&entry.path())?;
+    /// Merge a loaded config: entries whose name matches an existing language
+    /// extend it with extra markers/dirs, otherwise a new custom language is
+    /// appended.
+    fn merge(&mut self, config: Config) {
+        for entry in config.languages {
+            if let Some(existing) = self
+                .languages
+                .iter_mut()
+                .find(|language| language.kind.name().eq_ignore_ascii_case(&entry.name))
+            {
+                existing.markers.extend(entry.markers);
+                existing.extensions.extend(entry.extensions);
+                existing.dep_dirs.extend(entry.dep_dirs);
+            } else {
+                self.languages.push(Language {
+                    kind: RepoLanguage::Custom(entry.name),
+                    markers: entry.markers,
+                    extensions: entry.extensions,
+                    dep_dirs: entry.dep_dirs,
+                });
             }
-        } else {
-            size = file.metadata()?.size();
+        }
+    }
+
+    /// Classify the directory at `path` via a single scan of its entries,
+    /// returning the first language whose markers or extensions match.
+    fn detect(&self, path: &Path) -> Result<Option<RepoLanguage>, Box<dyn Error>> {
+        let mut names = Vec::new();
+        let mut extensions = Vec::new();
+
+        for entry in fs::read_dir(path)?.flatten() {
+            let name = entry.file_name();
+
+            if let Some(ext) = Path::new(&name).extension() {
+                extensions.push(ext.to_os_string());
+            }
+
+            names.push(name);
         }
 
-        Ok(size)
+        Ok(self
+            .languages
+            .iter()
+            .find(|language| {
+                names.iter().any(|name| {
+                    language.markers.iter().any(|m| name.eq_ignore_ascii_case(m))
+                }) || extensions.iter().any(|ext| {
+                    language.extensions.iter().any(|x| ext.eq_ignore_ascii_case(x))
+                })
+            })
+            .map(|language| language.kind.clone()))
+    }
+
+    fn spec(&self, kind: &RepoLanguage) -> Option<&Language> {
+        self.languages.iter().find(|language| &language.kind == kind)
+    }
+
+    fn dep_paths(&self, kind: &RepoLanguage, dir: &Path) -> Vec<PathBuf> {
+        self.spec(kind)
+            .map(|language| language.dep_paths(dir))
+            .unwrap_or_default()
+    }
+
+    /// Whether `name` is a dependency/build output directory of any known
+    /// language, honouring the table's `*.suffix` globs.
+    fn is_dep_dir(&self, name: &str) -> bool {
+        self.languages
+            .iter()
+            .any(|language| language.matches_dep_dir(name))
+    }
+}
+
+/// Contents of a `clean-deps.toml`: additional languages, or extensions to the
+/// built-in ones keyed by name.
+#[derive(Deserialize)]
+struct Config {
+    #[serde(default)]
+    languages: Vec<LanguageConfig>,
+}
+
+#[derive(Deserialize)]
+struct LanguageConfig {
+    name: String,
+    #[serde(default)]
+    markers: Vec<String>,
+    #[serde(default)]
+    extensions: Vec<String>,
+    #[serde(default)]
+    dep_dirs: Vec<String>,
+}
+
+/// Search for a `clean-deps.toml` by walking up from `root` to the filesystem
+/// root, returning the first one found.
+fn find_config(root: &Path) -> Option<PathBuf> {
+    let mut dir = Some(root);
+
+    while let Some(current) = dir {
+        let candidate = current.join("clean-deps.toml");
+
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+
+        dir = current.parent();
+    }
+
+    None
+}
+
+/// Newest mtime (seconds since the Unix epoch) of any file under `dir`,
+/// skipping every dependency/build directory the table recognises — including
+/// those of nested sub-packages — so vendored build output does not make an
+/// abandoned checkout look freshly edited.
+fn newest_source_mtime(dir: &Path, table: &LanguageTable) -> i64 {
+    let mut newest = 0;
+
+    let Ok(content) = fs::read_dir(dir) else {
+        return newest;
+    };
+
+    for entry in content.flatten() {
+        if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+            if table.is_dep_dir(&entry.file_name().to_string_lossy()) {
+                continue;
+            }
+
+            newest = newest.max(newest_source_mtime(&entry.path(), table));
+        } else if let Ok(metadata) = entry.metadata() {
+            newest = newest.max(metadata.mtime());
+        }
+    }
+
+    newest
+}
+
+fn get_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::metadata(path) else {
+        return 0;
+    };
+
+    if metadata.is_dir() {
+        let children: Vec<PathBuf> = match fs::read_dir(path) {
+            Ok(content) => content.flatten().map(|entry| entry.path()).collect(),
+            Err(_) => return 0,
+        };
+
+        children.par_iter().map(|child| get_size(child)).sum()
     } else {
-        Ok(0)
+        metadata.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("clean-deps-test-{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn csv_quote_doubles_inner_quotes() {
+        assert_eq!(csv_quote("/p/q"), "\"/p/q\"");
+        assert_eq!(csv_quote("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn detects_language_by_marker_and_extension() {
+        let table = LanguageTable::builtin();
+
+        let rust = temp_dir("detect-rust");
+        fs::write(rust.join("Cargo.toml"), "").unwrap();
+        assert_eq!(table.detect(&rust).unwrap(), Some(RepoLanguage::Rust));
+
+        let dotnet = temp_dir("detect-dotnet");
+        fs::write(dotnet.join("App.csproj"), "").unwrap();
+        assert_eq!(table.detect(&dotnet).unwrap(), Some(RepoLanguage::Dotnet));
+
+        let empty = temp_dir("detect-none");
+        assert_eq!(table.detect(&empty).unwrap(), None);
+
+        for dir in [rust, dotnet, empty] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn merge_extends_and_adds_languages() {
+        let mut table = LanguageTable::builtin();
+        table.merge(Config {
+            languages: vec![
+                LanguageConfig {
+                    name: "rust".into(),
+                    markers: vec![],
+                    extensions: vec![],
+                    dep_dirs: vec!["extra-out".into()],
+                },
+                LanguageConfig {
+                    name: "xcode".into(),
+                    markers: vec![],
+                    extensions: vec!["xcodeproj".into()],
+                    dep_dirs: vec!["DerivedData".into(), "Pods".into()],
+                },
+            ],
+        });
+
+        assert!(table.spec(&RepoLanguage::Rust).unwrap().matches_dep_dir("extra-out"));
+
+        let xcode = table
+            .spec(&RepoLanguage::Custom("xcode".into()))
+            .expect("custom language is added");
+        assert!(xcode.matches_dep_dir("Pods"));
+    }
+
+    #[test]
+    fn bare_star_dep_dir_matches_nothing() {
+        let language = Language::new(RepoLanguage::Custom("star".into()), &[], &[], &["*"]);
+        assert!(!language.matches_dep_dir("anything"));
+        assert!(!language.matches_dep_dir("src"));
+    }
+
+    #[test]
+    fn gitignore_keeps_only_concrete_names() {
+        let patterns = parse_gitignore("# comment\n*\n!keep\ntarget\nbuild/out\n");
+
+        assert_eq!(patterns.len(), 1);
+        assert!(patterns[0].matches("target"));
+        assert!(!patterns.iter().any(|pattern| pattern.matches("anything-else")));
+    }
+
+    #[test]
+    fn find_config_walks_up_to_the_root() {
+        let root = temp_dir("config-root");
+        let nested = root.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("clean-deps.toml"), "").unwrap();
+
+        assert_eq!(find_config(&nested), Some(root.join("clean-deps.toml")));
+
+        let bare = temp_dir("config-bare");
+        assert_eq!(find_config(&bare), None);
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&bare);
+    }
+
+    #[test]
+    fn repo_serializes_size_as_bytes_and_human() {
+        let repo = Repo {
+            language: RepoLanguage::Rust,
+            name: None,
+            dir: PathBuf::from("/a/b"),
+            deps_size: ByteSize(1024),
+            newest_mtime: 0,
+        };
+
+        let value = serde_json::to_value(&repo).unwrap();
+
+        assert_eq!(value["language"], "rust");
+        assert_eq!(value["dir"], "/a/b");
+        assert_eq!(value["deps_size"]["bytes"], 1024);
+        assert!(value["deps_size"]["human"].is_string());
     }
 }